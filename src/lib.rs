@@ -19,13 +19,14 @@
 
 use heck::SnakeCase;
 use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
 use quote::{format_ident, quote};
 use syn::{
     braced,
     parse::{Parse, ParseStream},
     parse_macro_input,
     punctuated::Punctuated,
-    Attribute, Ident, Token, Type, Visibility,
+    token, Attribute, Ident, Token, Type, Visibility,
 };
 
 /// Helper macro to compose existing types into an enum.
@@ -89,6 +90,22 @@ use syn::{
 /// // attempts to cast the enum to a specific variant
 /// let as_two: Option<&Two> = any.as_two();
 /// let as_three_mut: Option<&mut Three> = any.as_three_mut();
+///
+/// // attempts to move the inner value of a specific variant out of the enum
+/// let try_into_two: Option<Two> = any.try_into_two();
+/// ```
+///
+/// For ownership transfer without cloning, there are also `into_*` and `try_into_*` methods.
+/// The `into_*` variant hands the enum back as `Err(self)` if it holds a different variant,
+/// while `try_into_*` discards it in favor of a plain [`Option`].
+///
+/// ```
+/// # struct One;
+/// # struct Two;
+/// # struct Three;
+/// # unite::unite! { enum Any { One, Two, Three } }
+/// # let any: Any = Any::One(One);
+/// let into_one: Result<One, Any> = any.into_one();
 /// ```
 ///
 /// The generated enums also inherently implement [`From<Variant>`].
@@ -100,6 +117,100 @@ use syn::{
 /// # unite::unite! { enum Any { One, Two, Three } }
 /// let any: Any = One(true).into();
 /// ```
+///
+/// The conversion also works the other way around via [`TryFrom<Enum>`](std::convert::TryFrom).
+/// On mismatch the original enum is returned inside a generated `{Enum}TryFromError`.
+///
+/// ```
+/// # struct One(bool);
+/// # struct Two(i32);
+/// # struct Three(f64);
+/// # unite::unite! { enum Any { One, Two, Three } }
+/// use std::convert::TryFrom;
+///
+/// let any: Any = One(true).into();
+/// let one = One::try_from(any);
+/// assert!(one.is_ok());
+/// ```
+///
+/// ## Discriminants
+/// Adding `#[unite(discriminant = Kind)]` generates a companion fieldless enum `Kind` with one
+/// unit variant per variant of the main enum, plus a `kind` method to cheaply access it.
+///
+/// ```
+/// # struct One;
+/// # struct Two;
+/// # struct Three;
+/// unite::unite! {
+///     #[unite(discriminant = AnyKind)]
+///     enum Any { One, Two, Three }
+/// }
+///
+/// let any: Any = Any::One(One);
+/// let kind: AnyKind = any.kind();
+/// assert_eq!(kind, AnyKind::One);
+/// ```
+///
+/// ## Multiple & named fields
+/// Variants are not limited to a single type. `Variant = (A, B)` generates a variant with
+/// multiple unnamed fields, whose `as_*` helpers return tuples of references and whose
+/// `into_*` helpers return tuples of values.
+/// `Variant = { a: A, b: B }` generates a variant backed by a dedicated, generated struct.
+///
+/// ```
+/// # struct A;
+/// # struct B;
+/// unite::unite! {
+///     enum Any {
+///         Pair = (A, B),
+///         Point = { x: f64, y: f64 },
+///     }
+/// }
+///
+/// let pair: Any = (A, B).into();
+/// let (a, b): (&A, &B) = pair.as_pair().unwrap();
+///
+/// let point: Any = AnyPoint { x: 1.0, y: 2.0 }.into();
+/// let AnyPoint { x, y } = point.as_point().unwrap();
+/// ```
+///
+/// ## Reflection
+/// The generated enum also has a `COUNT` const with its number of variants and a `VARIANTS`
+/// const with their identifiers as strings. Combined with `#[unite(discriminant = Kind)]`,
+/// the discriminant enum also gets an `iter` function to iterate over all its variants.
+///
+/// ```
+/// # struct One;
+/// # struct Two;
+/// # struct Three;
+/// unite::unite! {
+///     #[unite(discriminant = AnyKind)]
+///     enum Any { One, Two, Three }
+/// }
+///
+/// assert_eq!(Any::COUNT, 3);
+/// assert_eq!(Any::VARIANTS, &["One", "Two", "Three"]);
+/// assert_eq!(AnyKind::iter().count(), 3);
+/// ```
+///
+/// ## Forwarding
+/// Adding `#[unite(forward(Display))]` generates a [`Display`](std::fmt::Display) impl for the
+/// enum that forwards formatting to whichever inner value is currently held, similar to
+/// `derive_more`. Other `std::fmt` traits work the same way. Forwarding does not support
+/// variants with multiple unnamed fields, since there is no single inner value to delegate
+/// to; variants with named fields forward to their generated struct.
+///
+/// ```
+/// use std::fmt::Display;
+///
+/// unite::unite! {
+///     #[unite(forward(Display))]
+///     enum Any { One = bool, Two = i32 }
+/// }
+///
+/// let any: Any = 42.into();
+/// assert_eq!(any.to_string(), "42");
+/// ```
 #[proc_macro]
 pub fn unite(input: TokenStream) -> TokenStream {
     // parse input
@@ -107,38 +218,75 @@ pub fn unite(input: TokenStream) -> TokenStream {
         attributes,
         visibility,
         name,
+        discriminant,
+        forward,
         variants,
     } = parse_macro_input!(input as Enum);
 
-    // generate type information for all enum variants
+    // generate type information for all enum variants, generating a dedicated struct
+    // for variants with named fields along the way
+    let mut named_field_structs = Vec::new();
     let variants_data = variants
         .into_iter()
         .map(
             |Variant {
                  attributes,
-                 name,
-                 ty,
+                 name: variant,
+                 fields,
              }| {
-                let ty = if let Some(ty) = &ty {
-                    quote! { #ty }
-                } else {
-                    quote! { #name }
+                let fields = match fields {
+                    VariantFields::Single(ty) => {
+                        let ty = if let Some(ty) = &ty {
+                            quote! { #ty }
+                        } else {
+                            quote! { #variant }
+                        };
+                        Fields::Single(ty)
+                    }
+                    VariantFields::Tuple(types) => {
+                        Fields::Tuple(types.iter().map(|ty| quote! { #ty }).collect())
+                    }
+                    VariantFields::Named(named) => {
+                        let struct_name = format_ident!("{}{}", name, variant);
+                        let struct_fields = named
+                            .iter()
+                            .map(|(field, ty)| quote! { pub #field: #ty });
+                        let struct_doc = format!(
+                            "Named fields of [`{name}::{variant}`]({name}::{variant}).",
+                            name = name,
+                            variant = variant,
+                        );
+
+                        named_field_structs.push(quote! {
+                            #[doc = #struct_doc]
+                            #[derive(Debug, Clone, PartialEq)]
+                            #visibility struct #struct_name {
+                                #(#struct_fields),*
+                            }
+                        });
+
+                        Fields::Single(quote! { #struct_name })
+                    }
                 };
-                (attributes, name, ty)
+                (attributes, variant, fields)
             },
         )
         .collect::<Vec<_>>();
 
     // generate enum variants
-    let variants = variants_data.iter().map(|(attributes, variant, ty)| {
+    let variants = variants_data.iter().map(|(attributes, variant, fields)| {
+        let decl = match fields {
+            Fields::Single(ty) => quote! { #ty },
+            Fields::Tuple(types) => quote! { #(#types),* },
+        };
         quote! {
             #(#attributes)*
-            #variant(#ty)
+            #variant(#decl)
         }
     });
 
     // generate helper functions
-    let funcs = variants_data.iter().map(|(_, variant, ty)| {
+    let funcs = variants_data.iter().map(|(_, variant, fields)| {
         // convert name to snake case
         let snake_case = variant.to_string().to_snake_case();
 
@@ -166,40 +314,257 @@ pub fn unite(input: TokenStream) -> TokenStream {
             variant = variant,
         );
 
-        quote! {
-            #[doc = #is_doc]
-            pub fn #is_name(&self) -> bool {
-                matches!(self, #name::#variant(_))
-            }
+        // generate into cast name & doc
+        let into_name = format_ident!("into_{}", snake_case);
+        let into_doc = format!(
+            "Attempts to convert this [`{name}`] into the underlying [`{variant}`]({name}::{variant}), returning the enum itself as [`Err`] if it is a different variant.",
+            name = name,
+            variant = variant,
+        );
+
+        // generate try into cast name & doc
+        let try_into_name = format_ident!("try_into_{}", snake_case);
+        let try_into_doc = format!(
+            "Attempts to convert this [`{name}`] into the underlying [`{variant}`]({name}::{variant}).",
+            name = name,
+            variant = variant,
+        );
 
-            #[doc = #as_doc]
-            pub fn #as_name(&self) -> Option<&#ty> {
-                if let #name::#variant(contents) = self {
-                    Some(contents)
-                } else {
-                    None
+        match fields {
+            Fields::Single(ty) => quote! {
+                #[doc = #is_doc]
+                pub fn #is_name(&self) -> bool {
+                    matches!(self, #name::#variant(_))
+                }
+
+                #[doc = #as_doc]
+                pub fn #as_name(&self) -> Option<&#ty> {
+                    if let #name::#variant(contents) = self {
+                        Some(contents)
+                    } else {
+                        None
+                    }
+                }
+
+                #[doc = #as_mut_doc]
+                pub fn #as_mut_name(&mut self) -> Option<&mut #ty> {
+                    if let #name::#variant(contents) = self {
+                        Some(contents)
+                    } else {
+                        None
+                    }
                 }
-            }
 
-            #[doc = #as_mut_doc]
-            pub fn #as_mut_name(&mut self) -> Option<&mut #ty> {
-                if let #name::#variant(contents) = self {
-                    Some(contents)
-                } else {
-                    None
+                #[doc = #into_doc]
+                pub fn #into_name(self) -> Result<#ty, Self> {
+                    if let #name::#variant(contents) = self {
+                        Ok(contents)
+                    } else {
+                        Err(self)
+                    }
+                }
+
+                #[doc = #try_into_doc]
+                pub fn #try_into_name(self) -> Option<#ty> {
+                    self.#into_name().ok()
+                }
+            },
+
+            Fields::Tuple(types) => {
+                let bindings = (0..types.len())
+                    .map(|index| format_ident!("field_{}", index))
+                    .collect::<Vec<_>>();
+
+                quote! {
+                    #[doc = #is_doc]
+                    pub fn #is_name(&self) -> bool {
+                        matches!(self, #name::#variant(..))
+                    }
+
+                    #[doc = #as_doc]
+                    pub fn #as_name(&self) -> Option<(#(&#types),*)> {
+                        if let #name::#variant(#(#bindings),*) = self {
+                            Some((#(#bindings),*))
+                        } else {
+                            None
+                        }
+                    }
+
+                    #[doc = #as_mut_doc]
+                    pub fn #as_mut_name(&mut self) -> Option<(#(&mut #types),*)> {
+                        if let #name::#variant(#(#bindings),*) = self {
+                            Some((#(#bindings),*))
+                        } else {
+                            None
+                        }
+                    }
+
+                    #[doc = #into_doc]
+                    pub fn #into_name(self) -> Result<(#(#types),*), Self> {
+                        if let #name::#variant(#(#bindings),*) = self {
+                            Ok((#(#bindings),*))
+                        } else {
+                            Err(self)
+                        }
+                    }
+
+                    #[doc = #try_into_doc]
+                    pub fn #try_into_name(self) -> Option<(#(#types),*)> {
+                        self.#into_name().ok()
+                    }
                 }
             }
         }
     });
 
+    // generate the `COUNT` & `VARIANTS` reflection consts
+    let variant_count = variants_data.len();
+    let variant_names = variants_data
+        .iter()
+        .map(|(_, variant, _)| variant.to_string());
+    let reflection_consts = quote! {
+        /// The number of variants.
+        pub const COUNT: usize = #variant_count;
+
+        /// The identifiers of all variants, in declaration order.
+        pub const VARIANTS: &'static [&'static str] = &[#(#variant_names),*];
+    };
+
+    // generate name for the shared TryFrom error type
+    let try_from_error_name = format_ident!("{}TryFromError", name);
+
     // generate helper impls
-    let impls = variants_data.iter().map(|(_, variant, ty)| {
-        quote! {
+    let impls = variants_data.iter().map(|(_, variant, fields)| match fields {
+        Fields::Single(ty) => quote! {
             impl From<#ty> for #name {
                 fn from(inner: #ty) -> Self {
                     Self::#variant(inner)
                 }
             }
+
+            impl std::convert::TryFrom<#name> for #ty {
+                type Error = #try_from_error_name;
+
+                fn try_from(value: #name) -> Result<Self, Self::Error> {
+                    if let #name::#variant(contents) = value {
+                        Ok(contents)
+                    } else {
+                        Err(#try_from_error_name(value))
+                    }
+                }
+            }
+        },
+
+        Fields::Tuple(types) => {
+            let bindings = (0..types.len())
+                .map(|index| format_ident!("field_{}", index))
+                .collect::<Vec<_>>();
+
+            quote! {
+                impl From<(#(#types),*)> for #name {
+                    fn from((#(#bindings),*): (#(#types),*)) -> Self {
+                        Self::#variant(#(#bindings),*)
+                    }
+                }
+
+                impl std::convert::TryFrom<#name> for (#(#types),*) {
+                    type Error = #try_from_error_name;
+
+                    fn try_from(value: #name) -> Result<Self, Self::Error> {
+                        if let #name::#variant(#(#bindings),*) = value {
+                            Ok((#(#bindings),*))
+                        } else {
+                            Err(#try_from_error_name(value))
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    // generate doc for the TryFrom error type
+    let try_from_error_doc = format!(
+        "Error returned when converting a [`{name}`] into one of its variants fails, carrying back the original [`{name}`].",
+        name = name,
+    );
+
+    // generate the companion discriminant enum & `kind` method, if opted into
+    let discriminant_impl = discriminant.map(|kind_name| {
+        let kind_variants = variants_data.iter().map(|(_, variant, _)| quote! { #variant });
+        let kind_arms = variants_data
+            .iter()
+            .map(|(_, variant, _)| quote! { #name::#variant(..) => #kind_name::#variant });
+        let kind_paths = variants_data
+            .iter()
+            .map(|(_, variant, _)| quote! { #kind_name::#variant });
+
+        let kind_doc = format!(
+            "Discriminant enum for [`{name}`], with one fieldless variant per [`{name}`] variant.",
+            name = name,
+        );
+        let kind_fn_doc = format!(
+            "Returns the [`{kind}`] discriminant of this [`{name}`].",
+            kind = kind_name,
+            name = name,
+        );
+        let iter_doc = format!(
+            "Returns an iterator over all [`{kind}`] variants.",
+            kind = kind_name,
+        );
+
+        quote! {
+            #[doc = #kind_doc]
+            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+            #visibility enum #kind_name {
+                #(#kind_variants),*
+            }
+
+            impl #name {
+                #[doc = #kind_fn_doc]
+                pub fn kind(&self) -> #kind_name {
+                    match self {
+                        #(#kind_arms),*
+                    }
+                }
+            }
+
+            impl #kind_name {
+                #[doc = #iter_doc]
+                pub fn iter() -> impl Iterator<Item = Self> + Clone {
+                    [#(#kind_paths),*].iter().copied()
+                }
+            }
+        }
+    });
+
+    // generate `fmt::Trait` impls that forward to the inner value, for each opted into trait
+    let forward_impls = forward.iter().map(|trait_name| {
+        let arms = variants_data.iter().map(|(_, variant, fields)| match fields {
+            Fields::Single(_) => quote! {
+                #name::#variant(inner) => std::fmt::#trait_name::fmt(inner, f)
+            },
+            // a tuple of references has no meaningful `fmt::Trait` impl of its own to forward
+            // to, so point the user at the variant instead of letting a cryptic trait-bound
+            // error from the generated code surface
+            Fields::Tuple(_) => {
+                let message = format!(
+                    "cannot forward `{}` for variant `{}`: forwarding does not support variants with multiple unnamed fields",
+                    trait_name, variant,
+                );
+                quote! {
+                    #name::#variant(..) => compile_error!(#message)
+                }
+            }
+        });
+
+        quote! {
+            impl std::fmt::#trait_name for #name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    match self {
+                        #(#arms),*
+                    }
+                }
+            }
         }
     });
 
@@ -211,10 +576,37 @@ pub fn unite(input: TokenStream) -> TokenStream {
         }
 
         impl #name {
+            #reflection_consts
+
             #(#funcs)*
         }
 
         #(#impls)*
+
+        #(#named_field_structs)*
+
+        #[doc = #try_from_error_doc]
+        #visibility struct #try_from_error_name(#name);
+
+        impl #try_from_error_name {
+            /// Returns the original enum that could not be converted into the variant.
+            pub fn into_inner(self) -> #name {
+                self.0
+            }
+        }
+
+        // manual `Debug` impl instead of `#[derive(Debug)]`: deriving on this single-field
+        // tuple struct would require #name itself to implement `Debug`, silently forcing
+        // that bound onto every enum built with `unite!`
+        impl std::fmt::Debug for #try_from_error_name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!(#try_from_error_name)).finish()
+            }
+        }
+
+        #discriminant_impl
+
+        #(#forward_impls)*
     };
 
     TokenStream::from(result)
@@ -224,12 +616,35 @@ struct Enum {
     attributes: Vec<Attribute>,
     visibility: Visibility,
     name: Ident,
+    discriminant: Option<Ident>,
+    forward: Vec<Ident>,
     variants: Punctuated<Variant, Token![,]>,
 }
 
 impl Parse for Enum {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        let attributes = input.call(Attribute::parse_outer)?;
+        let parsed_attributes = input.call(Attribute::parse_outer)?;
+
+        // split off `#[unite(..)]` attributes, which configure the macro itself,
+        // from the rest, which are passed through to the generated enum
+        let mut attributes = Vec::with_capacity(parsed_attributes.len());
+        let mut discriminant = None;
+        let mut forward = Vec::new();
+        for attribute in parsed_attributes {
+            if attribute.path.is_ident("unite") {
+                let options = attribute
+                    .parse_args_with(Punctuated::<UniteOption, Token![,]>::parse_terminated)?;
+                for option in options {
+                    match option {
+                        UniteOption::Discriminant(kind) => discriminant = Some(kind),
+                        UniteOption::Forward(traits) => forward.extend(traits),
+                    }
+                }
+            } else {
+                attributes.push(attribute);
+            }
+        }
+
         let visibility = input.parse()?;
         input.parse::<Token![enum]>()?;
         let name = input.parse()?;
@@ -242,31 +657,111 @@ impl Parse for Enum {
             attributes,
             visibility,
             name,
+            discriminant,
+            forward,
             variants,
         })
     }
 }
 
+/// A single option passed via the `#[unite(..)]` attribute on the enum.
+enum UniteOption {
+    /// `discriminant = Kind`
+    Discriminant(Ident),
+    /// `forward(Display, ..)`
+    Forward(Vec<Ident>),
+}
+
+impl Parse for UniteOption {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        if key == "discriminant" {
+            input.parse::<Token![=]>()?;
+            Ok(Self::Discriminant(input.parse()?))
+        } else if key == "forward" {
+            let inner;
+            syn::parenthesized!(inner in input);
+            let traits = inner.parse_terminated::<_, Token![,]>(Ident::parse)?;
+            Ok(Self::Forward(traits.into_iter().collect()))
+        } else {
+            Err(syn::Error::new(
+                key.span(),
+                "expected `discriminant` or `forward`",
+            ))
+        }
+    }
+}
+
 struct Variant {
     attributes: Vec<Attribute>,
     name: Ident,
-    ty: Option<Type>,
+    fields: VariantFields,
 }
 
 impl Parse for Variant {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let attributes = input.call(Attribute::parse_outer)?;
         let name = input.parse()?;
-        let ty = if input.peek(Token![=]) {
+
+        let fields = if input.peek(Token![=]) {
             input.parse::<Token![=]>()?;
-            Some(input.parse()?)
+
+            if input.peek(token::Brace) {
+                let inner;
+                braced!(inner in input);
+                let fields = inner.parse_terminated::<_, Token![,]>(NamedField::parse)?;
+                VariantFields::Named(fields.into_iter().map(|field| (field.name, field.ty)).collect())
+            } else {
+                match input.parse()? {
+                    // a parenthesized list of 2 or more types is a tuple of fields,
+                    // while `()` and a lone parenthesized type keep the existing single-type behavior
+                    Type::Tuple(tuple) if tuple.elems.len() >= 2 => {
+                        VariantFields::Tuple(tuple.elems.into_iter().collect())
+                    }
+                    ty => VariantFields::Single(Some(Box::new(ty))),
+                }
+            }
         } else {
-            None
+            VariantFields::Single(None)
         };
+
         Ok(Self {
             attributes,
             name,
-            ty,
+            fields,
         })
     }
 }
+
+/// The inner fields of a [`Variant`], in the shape they were declared in.
+// boxed to keep `Single` from dragging the size of every `VariantFields` up to that of a
+// lone `Type`, which otherwise dwarfs the `Vec`-backed `Tuple`/`Named` variants
+enum VariantFields {
+    /// A single field, either explicit (`Variant = Type`) or implicit (`Variant`).
+    Single(Option<Box<Type>>),
+    /// Multiple unnamed fields: `Variant = (A, B)`.
+    Tuple(Vec<Type>),
+    /// Named fields: `Variant = { a: A, b: B }`.
+    Named(Vec<(Ident, Type)>),
+}
+
+/// A single `name: Type` entry of a [`VariantFields::Named`] variant.
+struct NamedField {
+    name: Ident,
+    ty: Type,
+}
+
+impl Parse for NamedField {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name = input.parse()?;
+        input.parse::<Token![:]>()?;
+        let ty = input.parse()?;
+        Ok(Self { name, ty })
+    }
+}
+
+/// The resolved fields of a variant, after named fields have been hoisted into a generated struct.
+enum Fields {
+    Single(TokenStream2),
+    Tuple(Vec<TokenStream2>),
+}
@@ -1,3 +1,4 @@
+use std::convert::TryFrom;
 use unite::unite;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -8,16 +9,30 @@ struct Bar(bool);
 
 unite! {
     /// Test enum.
+    #[derive(Debug, PartialEq)]
+    #[unite(discriminant = TestKind)]
     enum Test {
         Foo,
         Baz = Bar,
         Void = (),
+        Pair = (Foo, Bar),
+        Point = { x: f64, y: f64 },
+    }
+}
+
+unite! {
+    #[unite(forward(Display, Debug))]
+    enum Formatted {
+        Int = i32,
+        Bool = bool,
     }
 }
 
 const FOO: Test = Test::Foo(Foo(0));
 const BAZ: Test = Test::Baz(Bar(true));
 const VOID: Test = Test::Void(());
+const PAIR: Test = Test::Pair(Foo(1), Bar(false));
+const POINT: Test = Test::Point(TestPoint { x: 1.0, y: 2.0 });
 
 #[test]
 fn checks() {
@@ -48,3 +63,129 @@ fn casting() {
     assert_eq!(VOID.as_baz(), None);
     assert_eq!(VOID.as_void(), Some(&()));
 }
+
+#[test]
+fn into() {
+    assert_eq!(FOO.into_foo(), Ok(Foo(0)));
+    assert_eq!(BAZ.into_foo(), Err(BAZ));
+    assert_eq!(VOID.into_foo(), Err(VOID));
+
+    assert_eq!(FOO.try_into_baz(), None);
+    assert_eq!(BAZ.try_into_baz(), Some(Bar(true)));
+    assert_eq!(VOID.try_into_baz(), None);
+
+    assert_eq!(FOO.try_into_void(), None);
+    assert_eq!(BAZ.try_into_void(), None);
+    assert_eq!(VOID.try_into_void(), Some(()));
+}
+
+#[test]
+fn try_from() {
+    assert_eq!(Foo::try_from(FOO).unwrap(), Foo(0));
+    assert_eq!(Bar::try_from(FOO).unwrap_err().into_inner(), FOO);
+
+    assert_eq!(Bar::try_from(BAZ).unwrap(), Bar(true));
+    assert_eq!(Foo::try_from(BAZ).unwrap_err().into_inner(), BAZ);
+}
+
+unite! {
+    // deliberately no `#[derive(Debug, ..)]` here: the generated error type must not
+    // require the enum itself to implement `Debug` or `PartialEq`
+    enum NoDerive {
+        Foo,
+        Num = i32,
+    }
+}
+
+#[test]
+fn try_from_without_derive() {
+    let foo: NoDerive = Foo(0).into();
+    assert_eq!(Foo::try_from(foo).unwrap(), Foo(0));
+
+    let any: NoDerive = 42.into();
+    let err = Foo::try_from(any).unwrap_err();
+    assert_eq!(format!("{:?}", err), "NoDeriveTryFromError");
+}
+
+#[test]
+fn kind() {
+    assert_eq!(FOO.kind(), TestKind::Foo);
+    assert_eq!(BAZ.kind(), TestKind::Baz);
+    assert_eq!(VOID.kind(), TestKind::Void);
+
+    assert_ne!(FOO.kind(), TestKind::Baz);
+}
+
+#[test]
+fn reflection() {
+    assert_eq!(Test::COUNT, 5);
+    assert_eq!(
+        Test::VARIANTS,
+        &["Foo", "Baz", "Void", "Pair", "Point"]
+    );
+
+    let kinds: Vec<_> = TestKind::iter().collect();
+    assert_eq!(
+        kinds,
+        vec![
+            TestKind::Foo,
+            TestKind::Baz,
+            TestKind::Void,
+            TestKind::Pair,
+            TestKind::Point,
+        ]
+    );
+}
+
+#[test]
+fn tuple_fields() {
+    assert_eq!(PAIR.is_pair(), true);
+    assert_eq!(FOO.is_pair(), false);
+
+    assert_eq!(PAIR.as_pair(), Some((&Foo(1), &Bar(false))));
+    assert_eq!(FOO.as_pair(), None);
+
+    assert_eq!(PAIR.into_pair(), Ok((Foo(1), Bar(false))));
+    assert_eq!(FOO.into_pair(), Err(FOO));
+
+    let from: Test = (Foo(2), Bar(true)).into();
+    assert_eq!(from, Test::Pair(Foo(2), Bar(true)));
+}
+
+#[test]
+fn forwarding() {
+    let int: Formatted = 42.into();
+    let boolean: Formatted = true.into();
+
+    assert_eq!(int.to_string(), "42");
+    assert_eq!(boolean.to_string(), "true");
+
+    assert_eq!(format!("{:?}", int), "42");
+    assert_eq!(format!("{:?}", boolean), "true");
+
+    assert_eq!(i32::try_from(int).unwrap(), 42);
+    assert_eq!(bool::try_from(boolean).unwrap(), true);
+
+    let mismatched: Formatted = 7.into();
+    assert_eq!(
+        bool::try_from(mismatched).unwrap_err().into_inner().to_string(),
+        "7"
+    );
+}
+
+#[test]
+fn named_fields() {
+    assert_eq!(POINT.is_point(), true);
+    assert_eq!(FOO.is_point(), false);
+
+    assert_eq!(POINT.as_point(), Some(&TestPoint { x: 1.0, y: 2.0 }));
+    assert_eq!(FOO.as_point(), None);
+
+    assert_eq!(
+        POINT.into_point(),
+        Ok(TestPoint { x: 1.0, y: 2.0 })
+    );
+
+    let from: Test = TestPoint { x: 3.0, y: 4.0 }.into();
+    assert_eq!(from, Test::Point(TestPoint { x: 3.0, y: 4.0 }));
+}